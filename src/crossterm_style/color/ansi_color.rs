@@ -0,0 +1,266 @@
+//! This module contains the ANSI specific implementation for coloring the terminal.
+
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::io::{self, Write};
+
+use Construct;
+use super::{Attribute, Color, ColorSnapshot, ColorSupport, ITerminalColor};
+
+/// This struct is an ANSI implementation for color related actions.
+///
+/// ANSI has no way to read back the colors and attributes that are currently in effect, so
+/// this tracks the last values it has set itself, for `snapshot()` to report.
+pub struct ANSIColor {
+    fg: Cell<Option<Color>>,
+    bg: Cell<Option<Color>>,
+    attrs: RefCell<Vec<Attribute>>,
+}
+
+impl Construct for ANSIColor {
+    fn new() -> Box<ANSIColor> {
+        Box::from(ANSIColor {
+            fg: Cell::new(None),
+            bg: Cell::new(None),
+            attrs: RefCell::new(Vec::new()),
+        })
+    }
+}
+
+impl ITerminalColor for ANSIColor {
+    fn set_fg(&self, fg_color: Color) {
+        let fg_color = match self.downgrade(fg_color) {
+            Some(fg_color) => fg_color,
+            None => return,
+        };
+        self.write(format!("\x1B[{}m", ansi_value(fg_color, true)));
+        self.fg.set(Some(fg_color));
+    }
+
+    fn set_bg(&self, bg_color: Color) {
+        let bg_color = match self.downgrade(bg_color) {
+            Some(bg_color) => bg_color,
+            None => return,
+        };
+        self.write(format!("\x1B[{}m", ansi_value(bg_color, false)));
+        self.bg.set(Some(bg_color));
+    }
+
+    fn reset(&self) {
+        self.write("\x1B[0m".to_string());
+        self.fg.set(None);
+        self.bg.set(None);
+        self.attrs.borrow_mut().clear();
+    }
+
+    fn reset_fg(&self) {
+        self.write("\x1B[39m".to_string());
+        self.fg.set(None);
+    }
+
+    fn reset_bg(&self) {
+        self.write("\x1B[49m".to_string());
+        self.bg.set(None);
+    }
+
+    fn set_attr(&self, attr: Attribute) {
+        self.write(format!("\x1B[{}m", attribute_set_code(attr)));
+        let mut attrs = self.attrs.borrow_mut();
+        if !attrs.contains(&attr) {
+            attrs.push(attr);
+        }
+    }
+
+    fn reset_attr(&self, attr: Attribute) {
+        self.write(format!("\x1B[{}m", attribute_reset_code(attr)));
+        self.attrs.borrow_mut().retain(|a| *a != attr);
+    }
+
+    fn color_support(&self) -> ColorSupport {
+        if !atty::is(atty::Stream::Stdout) {
+            return ColorSupport::NoColor;
+        }
+
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+
+        let term = env::var("TERM").unwrap_or_default();
+        if term.ends_with("-256color") {
+            return ColorSupport::Ansi256;
+        }
+
+        ColorSupport::Ansi16
+    }
+
+    fn snapshot(&self) -> ColorSnapshot {
+        ColorSnapshot {
+            fg: self.fg.get(),
+            bg: self.bg.get(),
+            attrs: self.attrs.borrow().clone(),
+        }
+    }
+}
+
+/// The SGR code that turns the given attribute on.
+fn attribute_set_code(attr: Attribute) -> u8 {
+    match attr {
+        Attribute::Bold => 1,
+        Attribute::Dim => 2,
+        Attribute::Italic => 3,
+        Attribute::Underlined => 4,
+        Attribute::SlowBlink => 5,
+        Attribute::Reverse => 7,
+        Attribute::Hidden => 8,
+        Attribute::Crossedout => 9,
+    }
+}
+
+/// The SGR code that turns the given attribute back off.
+fn attribute_reset_code(attr: Attribute) -> u8 {
+    match attr {
+        Attribute::Bold | Attribute::Dim => 22,
+        Attribute::Italic => 23,
+        Attribute::Underlined => 24,
+        Attribute::SlowBlink => 25,
+        Attribute::Reverse => 27,
+        Attribute::Hidden => 28,
+        Attribute::Crossedout => 29,
+    }
+}
+
+impl ANSIColor {
+    /// Write the given ANSI escape sequence to stdout.
+    fn write(&self, sequence: String) {
+        print!("{}", sequence);
+        let _ = io::stdout().flush();
+    }
+
+    /// Downgrade an RGB or 256-color value to the best representation the current terminal
+    /// actually supports, leaving the 15 named colors untouched. Returns `None` when the
+    /// terminal has no color support at all, meaning nothing should be written.
+    fn downgrade(&self, color: Color) -> Option<Color> {
+        match (color, self.color_support()) {
+            (_, ColorSupport::NoColor) => None,
+            (Color::Rgb { .. }, ColorSupport::TrueColor) => Some(color),
+            (Color::Rgb { r, g, b }, ColorSupport::Ansi256) => {
+                Some(Color::AnsiValue(rgb_to_ansi256(r, g, b)))
+            }
+            (Color::Rgb { r, g, b }, ColorSupport::Ansi16) => Some(nearest_ansi16(r, g, b)),
+            (Color::AnsiValue(_), ColorSupport::TrueColor)
+            | (Color::AnsiValue(_), ColorSupport::Ansi256) => Some(color),
+            (Color::AnsiValue(val), ColorSupport::Ansi16) => {
+                Some(nearest_ansi16_from_ansi_value(val))
+            }
+            (named, _) => Some(named),
+        }
+    }
+}
+
+/// Convert an RGB value into the nearest index of the 6x6x6 color cube in the 256-color
+/// palette (indices 16..=231).
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let to_cube = |c: u8| ((c as u16 * 5 + 127) / 255) as u8;
+    16 + 36 * to_cube(r) + 6 * to_cube(g) + to_cube(b)
+}
+
+/// Find the named ANSI color closest to the given RGB value, measured by squared
+/// Euclidean distance.
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    const PALETTE: [(Color, u8, u8, u8); 15] = [
+        (Color::Black, 0, 0, 0),
+        (Color::DarkBlue, 0, 0, 128),
+        (Color::DarkGreen, 0, 128, 0),
+        (Color::DarkCyan, 0, 128, 128),
+        (Color::DarkRed, 128, 0, 0),
+        (Color::DarkMagenta, 128, 0, 128),
+        (Color::DarkYellow, 128, 128, 0),
+        (Color::Grey, 192, 192, 192),
+        (Color::Blue, 0, 0, 255),
+        (Color::Green, 0, 255, 0),
+        (Color::Cyan, 0, 255, 255),
+        (Color::Red, 255, 0, 0),
+        (Color::Magenta, 255, 0, 255),
+        (Color::Yellow, 255, 255, 0),
+        (Color::White, 255, 255, 255),
+    ];
+
+    let mut nearest = Color::White;
+    let mut nearest_distance = u32::max_value();
+
+    for &(candidate, pr, pg, pb) in PALETTE.iter() {
+        let dr = r as i32 - pr as i32;
+        let dg = g as i32 - pg as i32;
+        let db = b as i32 - pb as i32;
+        let distance = (dr * dr + dg * dg + db * db) as u32;
+
+        if distance < nearest_distance {
+            nearest = candidate;
+            nearest_distance = distance;
+        }
+    }
+
+    nearest
+}
+
+/// Resolve a 256-color palette index down to the nearest named ANSI color.
+fn nearest_ansi16_from_ansi_value(val: u8) -> Color {
+    if val < 16 {
+        return match val {
+            0 => Color::Black,
+            1 => Color::DarkRed,
+            2 => Color::DarkGreen,
+            3 => Color::DarkYellow,
+            4 => Color::DarkBlue,
+            5 => Color::DarkMagenta,
+            6 => Color::DarkCyan,
+            7 => Color::Grey,
+            8 => Color::Black,
+            9 => Color::Red,
+            10 => Color::Green,
+            11 => Color::Yellow,
+            12 => Color::Blue,
+            13 => Color::Magenta,
+            14 => Color::Cyan,
+            _ => Color::White,
+        };
+    }
+
+    if val <= 231 {
+        let idx = val - 16;
+        let r = idx / 36;
+        let g = (idx / 6) % 6;
+        let b = idx % 6;
+        return nearest_ansi16(r * 51, g * 51, b * 51);
+    }
+
+    let grey = 8 + 10 * (val - 232);
+    nearest_ansi16(grey, grey, grey)
+}
+
+/// Convert the given color into the ANSI SGR parameters that set it as either the foreground
+/// (`fg == true`) or the background color.
+fn ansi_value(color: Color, fg: bool) -> String {
+    let base = if fg { 38 } else { 48 };
+
+    match color {
+        Color::Black => (if fg { 30 } else { 40 }).to_string(),
+        Color::DarkRed => (if fg { 31 } else { 41 }).to_string(),
+        Color::DarkGreen => (if fg { 32 } else { 42 }).to_string(),
+        Color::DarkYellow => (if fg { 33 } else { 43 }).to_string(),
+        Color::DarkBlue => (if fg { 34 } else { 44 }).to_string(),
+        Color::DarkMagenta => (if fg { 35 } else { 45 }).to_string(),
+        Color::DarkCyan => (if fg { 36 } else { 46 }).to_string(),
+        Color::Grey => (if fg { 37 } else { 47 }).to_string(),
+        Color::Red => (if fg { 91 } else { 101 }).to_string(),
+        Color::Green => (if fg { 92 } else { 102 }).to_string(),
+        Color::Yellow => (if fg { 93 } else { 103 }).to_string(),
+        Color::Blue => (if fg { 94 } else { 104 }).to_string(),
+        Color::Magenta => (if fg { 95 } else { 105 }).to_string(),
+        Color::Cyan => (if fg { 96 } else { 106 }).to_string(),
+        Color::White => (if fg { 97 } else { 107 }).to_string(),
+        Color::Rgb { r, g, b } => format!("{};2;{};{};{}", base, r, g, b),
+        Color::AnsiValue(val) => format!("{};5;{}", base, val),
+    }
+}
@@ -0,0 +1,41 @@
+//! This module contains the platform independent color logic. `ITerminalColor` defines the
+//! actions a platform specific color implementation has to support.
+
+use super::{Attribute, Color, ColorSupport};
+
+/// A snapshot of the colors and attributes that are currently in effect, so they can later
+/// be restored. `None` means no color has been explicitly set, i.e. the terminal's own
+/// default foreground/background color is in effect.
+#[derive(Debug, Clone)]
+pub struct ColorSnapshot {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub attrs: Vec<Attribute>,
+}
+
+/// This trait defines the actions that can be performed with the terminal color.
+/// This trait can be implemented so that a concrete implementation of the ITerminalColor can
+/// forfill the wishes to work on both unix and windows systems.
+pub trait ITerminalColor {
+    /// Set the foreground color to the given color.
+    fn set_fg(&self, fg_color: Color);
+    /// Set the background color to the given color.
+    fn set_bg(&self, bg_color: Color);
+    /// Reset the terminal colors and attributes to default.
+    fn reset(&self);
+    /// Reset just the foreground color to the terminal's own default, leaving the
+    /// background color and attributes untouched.
+    fn reset_fg(&self);
+    /// Reset just the background color to the terminal's own default, leaving the
+    /// foreground color and attributes untouched.
+    fn reset_bg(&self);
+    /// Set the given text attribute, like bold or underlined.
+    fn set_attr(&self, attr: Attribute);
+    /// Reset the given text attribute, leaving colors untouched.
+    fn reset_attr(&self, attr: Attribute);
+    /// Get the level of color support the current terminal offers.
+    fn color_support(&self) -> ColorSupport;
+    /// Capture the colors and attributes that are currently in effect, so they can be
+    /// restored later on with a `ColorGuard`.
+    fn snapshot(&self) -> ColorSnapshot;
+}
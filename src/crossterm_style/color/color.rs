@@ -7,7 +7,7 @@ use std::str::FromStr;
 
 use Construct;
 use crossterm_style::{ObjectStyle, StyledObject};
-use super::base_color::ITerminalColor;
+use super::base_color::{ColorSnapshot, ITerminalColor};
 
 #[cfg(unix)]
 use super::ANSIColor;
@@ -39,6 +39,11 @@ pub enum Color {
 
     Grey,
     White,
+
+    /// A true-color (24-bit) value.
+    Rgb { r: u8, g: u8, b: u8 },
+    /// A color from the 256-color (8-bit) ANSI palette.
+    AnsiValue(u8),
 }
 
 /// Color types that can be used to determine if the Color enum is an Fore- or Background Color
@@ -60,34 +65,126 @@ impl From<String> for Color {
     }
 }
 
+/// The error returned when a string could not be parsed into a `Color`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a valid color", self.0)
+    }
+}
+
 impl FromStr for Color {
-    type Err = ();
+    type Err = ParseColorError;
 
     fn from_str(src: &str) -> Result<Self, Self::Err> {
-        let src = src.to_lowercase();
-
-        match src.as_ref() {
-            "black" => Ok(Color::Black),
-            "red" => Ok(Color::Red),
-            "dark_red" => Ok(Color::DarkRed),
-            "green" => Ok(Color::Green),
-            "dark_green" => Ok(Color::DarkGreen),
-            "yellow" => Ok(Color::Yellow),
-            "dark_yellow" => Ok(Color::DarkYellow),
-            "blue" => Ok(Color::Blue),
-            "dark_blue" => Ok(Color::DarkBlue),
-            "magenta" => Ok(Color::Magenta),
-            "dark_magenta" => Ok(Color::DarkMagenta),
-            "cyan" => Ok(Color::Cyan),
-            "dark_cyan" => Ok(Color::DarkCyan),
-            "grey" => Ok(Color::Grey),
-            "white" => Ok(Color::White),
-            _ => Ok(Color::White),
-        }
-    }
-}
-
-/// Struct that stores an specific platform implementation for color related actions. 
+        let lower = src.to_lowercase();
+
+        let color = match lower.as_ref() {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "dark_red" => Color::DarkRed,
+            "green" => Color::Green,
+            "dark_green" => Color::DarkGreen,
+            "yellow" => Color::Yellow,
+            "dark_yellow" => Color::DarkYellow,
+            "blue" => Color::Blue,
+            "dark_blue" => Color::DarkBlue,
+            "magenta" => Color::Magenta,
+            "dark_magenta" => Color::DarkMagenta,
+            "cyan" => Color::Cyan,
+            "dark_cyan" => Color::DarkCyan,
+            "grey" => Color::Grey,
+            "white" => Color::White,
+            _ => return parse_rgb(&lower).ok_or_else(|| ParseColorError(src.to_string())),
+        };
+
+        Ok(color)
+    }
+}
+
+/// Parse `#rrggbb`, `#rgb` and `rgb(r, g, b)` forms into `Color::Rgb`.
+fn parse_rgb(src: &str) -> Option<Color> {
+    if let Some(hex) = src.strip_prefix('#') {
+        return match hex.len() {
+            6 => {
+                let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+                let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+                let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+                Some(Color::Rgb { r, g, b })
+            }
+            3 => {
+                let r = u8::from_str_radix(&hex[0..1].repeat(2), 16).ok()?;
+                let g = u8::from_str_radix(&hex[1..2].repeat(2), 16).ok()?;
+                let b = u8::from_str_radix(&hex[2..3].repeat(2), 16).ok()?;
+                Some(Color::Rgb { r, g, b })
+            }
+            _ => None,
+        };
+    }
+
+    if src.starts_with("rgb(") && src.ends_with(')') {
+        let inner = &src["rgb(".len()..src.len() - 1];
+        let mut parts = inner.split(',').map(|part| part.trim().parse::<u8>());
+
+        let r = parts.next()?.ok()?;
+        let g = parts.next()?.ok()?;
+        let b = parts.next()?.ok()?;
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        return Some(Color::Rgb { r, g, b });
+    }
+
+    None
+}
+
+/// Attributes that can be applied to the font, on top of the foreground and background color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Attribute {
+    Bold,
+    Dim,
+    Italic,
+    Underlined,
+    SlowBlink,
+    Reverse,
+    Hidden,
+    Crossedout,
+}
+
+impl Attribute {
+    /// All the attribute variants, in the order they should be restored by a `ColorGuard`.
+    fn all() -> [Attribute; 8] {
+        [
+            Attribute::Bold,
+            Attribute::Dim,
+            Attribute::Italic,
+            Attribute::Underlined,
+            Attribute::SlowBlink,
+            Attribute::Reverse,
+            Attribute::Hidden,
+            Attribute::Crossedout,
+        ]
+    }
+}
+
+/// The level of color support the current terminal offers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// No color support at all, e.g. output is not a tty.
+    NoColor,
+    /// The 16 named ANSI colors.
+    Ansi16,
+    /// The 256-color (8-bit) ANSI palette.
+    Ansi256,
+    /// True-color (24-bit RGB).
+    TrueColor,
+}
+
+/// Struct that stores an specific platform implementation for color related actions.
 pub struct TerminalColor {
     terminal_color: Option<Box<ITerminalColor>>,
 }
@@ -107,7 +204,7 @@ impl TerminalColor {
     /// ```rust
     /// extern crate crossterm;
     ///
-    /// use self::crossterm::crossterm_style::{ get, Color};
+    /// use self::crossterm::{ get, Color };
     ///
     /// // Get colored terminal instance
     /// let mut colored_terminal = get();
@@ -133,7 +230,7 @@ impl TerminalColor {
     ///
     /// extern crate crossterm;
     ///
-    /// use self::crossterm::crossterm_style::{ get, Color};
+    /// use self::crossterm::{ get, Color };
     ///
     /// // Get colored terminal instance
     /// let mut colored_terminal = get();
@@ -157,7 +254,7 @@ impl TerminalColor {
     /// ```rust
     /// extern crate crossterm;
     ///
-    /// use self::crossterm::crossterm_style::get;
+    /// use self::crossterm::get;
     ///
     /// // Get colored terminal instance
     /// let mut colored_terminal = get();
@@ -171,6 +268,188 @@ impl TerminalColor {
             terminal_color.reset();
         }
     }
+
+    /// Set the given text attribute, like `Attribute::Bold` or `Attribute::Underlined`.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::{ get, Attribute };
+    ///
+    /// // Get colored terminal instance
+    /// let mut colored_terminal = get();
+    ///
+    /// colored_terminal.set_attr(Attribute::Bold);
+    ///
+    /// ```
+    pub fn set_attr(&mut self, attr: Attribute) {
+        &self.init();
+        if let Some(ref terminal_color) = self.terminal_color {
+            terminal_color.set_attr(attr);
+        }
+    }
+
+    /// Reset the given text attribute, leaving colors untouched.
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::{ get, Attribute };
+    ///
+    /// // Get colored terminal instance
+    /// let mut colored_terminal = get();
+    ///
+    /// colored_terminal.reset_attr(Attribute::Bold);
+    ///
+    /// ```
+    pub fn reset_attr(&mut self, attr: Attribute) {
+        &self.init();
+        if let Some(ref terminal_color) = self.terminal_color {
+            terminal_color.reset_attr(attr);
+        }
+    }
+
+    /// Get the level of color support the current terminal offers, so `set_fg`/`set_bg`
+    /// can be used without emitting sequences the terminal won't understand.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::get;
+    ///
+    /// // Get colored terminal instance
+    /// let mut colored_terminal = get();
+    ///
+    /// let support = colored_terminal.color_support();
+    ///
+    /// ```
+    pub fn color_support(&mut self) -> ColorSupport {
+        &self.init();
+        match self.terminal_color {
+            Some(ref terminal_color) => terminal_color.color_support(),
+            None => ColorSupport::NoColor,
+        }
+    }
+
+    /// Set the foreground color, returning a `ColorGuard` that restores the previous
+    /// foreground color, background color and attributes once it is dropped.
+    ///
+    /// This scopes a color change to a lexical block, so a panic or early return mid-render
+    /// can no longer leave the terminal in a colored state.
+    ///
+    /// #Example
+    ///
+    /// ```rust
+    /// extern crate crossterm;
+    ///
+    /// use self::crossterm::{ get, Color };
+    ///
+    /// // Get colored terminal instance
+    /// let mut colored_terminal = get();
+    ///
+    /// {
+    ///     let _guard = colored_terminal.with_fg(Color::Red);
+    ///     // prints in red, then is restored once `_guard` goes out of scope
+    /// }
+    ///
+    /// ```
+    pub fn with_fg(&mut self, color: Color) -> ColorGuard {
+        let snapshot = self.snapshot();
+        self.set_fg(color);
+        ColorGuard {
+            terminal: self,
+            snapshot,
+        }
+    }
+
+    /// Set the background color, returning a `ColorGuard` that restores the previous
+    /// foreground color, background color and attributes once it is dropped.
+    pub fn with_bg(&mut self, color: Color) -> ColorGuard {
+        let snapshot = self.snapshot();
+        self.set_bg(color);
+        ColorGuard {
+            terminal: self,
+            snapshot,
+        }
+    }
+
+    /// Capture the colors and attributes that are currently in effect.
+    fn snapshot(&mut self) -> ColorSnapshot {
+        &self.init();
+        match self.terminal_color {
+            Some(ref terminal_color) => terminal_color.snapshot(),
+            None => ColorSnapshot {
+                fg: None,
+                bg: None,
+                attrs: Vec::new(),
+            },
+        }
+    }
+
+    /// Reset just the foreground color to the terminal's own default.
+    fn reset_fg(&mut self) {
+        &self.init();
+        if let Some(ref terminal_color) = self.terminal_color {
+            terminal_color.reset_fg();
+        }
+    }
+
+    /// Reset just the background color to the terminal's own default.
+    fn reset_bg(&mut self) {
+        &self.init();
+        if let Some(ref terminal_color) = self.terminal_color {
+            terminal_color.reset_bg();
+        }
+    }
+}
+
+/// An RAII guard that restores the foreground color, background color and attributes that
+/// were in effect before it was created, once it is dropped. Returned by `TerminalColor::with_fg`
+/// and `TerminalColor::with_bg`.
+pub struct ColorGuard<'a> {
+    terminal: &'a mut TerminalColor,
+    snapshot: ColorSnapshot,
+}
+
+impl<'a> Drop for ColorGuard<'a> {
+    fn drop(&mut self) {
+        match self.snapshot.fg {
+            Some(fg) => self.terminal.set_fg(fg),
+            None => self.terminal.reset_fg(),
+        }
+        match self.snapshot.bg {
+            Some(bg) => self.terminal.set_bg(bg),
+            None => self.terminal.reset_bg(),
+        }
+
+        // Bold and Dim share a single SGR reset code (22), so restoring them independently
+        // can clear one while re-establishing the other; decide the intensity as one unit.
+        let bold = self.snapshot.attrs.contains(&Attribute::Bold);
+        let dim = self.snapshot.attrs.contains(&Attribute::Dim);
+        if bold {
+            self.terminal.set_attr(Attribute::Bold);
+        } else if dim {
+            self.terminal.set_attr(Attribute::Dim);
+        } else {
+            self.terminal.reset_attr(Attribute::Bold);
+        }
+
+        for attr in Attribute::all()
+            .iter()
+            .filter(|a| **a != Attribute::Bold && **a != Attribute::Dim)
+        {
+            if self.snapshot.attrs.contains(attr) {
+                self.terminal.set_attr(*attr);
+            } else {
+                self.terminal.reset_attr(*attr);
+            }
+        }
+    }
 }
 
 /// Get an concrete ITerminalColor implementation based on the current operating system.
@@ -188,7 +467,7 @@ fn get_color_options() -> Option<Box<ITerminalColor>> {
 /// ```rust
 /// extern crate crossterm;
 ///
-/// use self::crossterm::crossterm_style::{get, Color};
+/// use self::crossterm::{get, Color};
 /// 
 /// // Get colored terminal instance
 /// let mut colored_terminal = get();
@@ -213,7 +492,7 @@ pub fn get() -> Box<TerminalColor> {
 /// ```rust
 /// extern crate crossterm;
 ///
-/// use self::crossterm::crossterm_style::{paint,Color};
+/// use self::crossterm::{paint,Color};
 ///
 /// fn main()
 /// {
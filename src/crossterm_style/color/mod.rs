@@ -0,0 +1,20 @@
+//! This module contains all the logic for coloring the terminal.
+
+mod base_color;
+mod color;
+
+#[cfg(unix)]
+mod ansi_color;
+#[cfg(windows)]
+mod winapi_color;
+
+pub use self::base_color::{ColorSnapshot, ITerminalColor};
+pub use self::color::{
+    get, paint, Attribute, Color, ColorGuard, ColorSupport, ColorType, ParseColorError,
+    TerminalColor,
+};
+
+#[cfg(unix)]
+pub use self::ansi_color::ANSIColor;
+#[cfg(windows)]
+pub use self::winapi_color::WinApiColor;
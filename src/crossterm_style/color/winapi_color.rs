@@ -0,0 +1,329 @@
+//! This module contains the WinApi specific implementation for coloring the terminal.
+
+use std::cell::Cell;
+
+use kernel32::{
+    GetConsoleMode, GetConsoleScreenBufferInfo, GetStdHandle, SetConsoleMode,
+    SetConsoleTextAttribute,
+};
+use winapi::{CONSOLE_SCREEN_BUFFER_INFO, DWORD, HANDLE, STD_OUTPUT_HANDLE, WORD};
+use winapi::wincon::{
+    COMMON_LVB_REVERSE_VIDEO, COMMON_LVB_UNDERSCORE, FOREGROUND_BLUE, FOREGROUND_GREEN,
+    FOREGROUND_INTENSITY, FOREGROUND_RED,
+};
+
+use Construct;
+use super::{Attribute, Color, ColorSnapshot, ColorSupport, ITerminalColor};
+
+/// Not exposed by older `winapi` versions, so it is defined here instead.
+const ENABLE_VIRTUAL_TERMINAL_PROCESSING: DWORD = 0x0004;
+
+/// The console only understands its 16 colors, of which `Color` names the 15 below (it has
+/// no dark grey), so a RGB or 256-color value is down-quantized to whichever of these is
+/// closest. Dark grey is never chosen.
+const CONSOLE_COLORS: [(Color, u8, u8, u8); 15] = [
+    (Color::Black, 0, 0, 0),
+    (Color::DarkBlue, 0, 0, 128),
+    (Color::DarkGreen, 0, 128, 0),
+    (Color::DarkCyan, 0, 128, 128),
+    (Color::DarkRed, 128, 0, 0),
+    (Color::DarkMagenta, 128, 0, 128),
+    (Color::DarkYellow, 128, 128, 0),
+    (Color::Grey, 192, 192, 192),
+    (Color::Blue, 0, 0, 255),
+    (Color::Green, 0, 255, 0),
+    (Color::Cyan, 0, 255, 255),
+    (Color::Red, 255, 0, 0),
+    (Color::Magenta, 255, 0, 255),
+    (Color::Yellow, 255, 255, 0),
+    (Color::White, 255, 255, 255),
+];
+
+/// This struct is a WinApi implementation for color related actions.
+///
+/// `default_attribute` is the console text attribute that was in effect before crossterm
+/// touched anything, captured once at construction, so `snapshot()` can tell a color that
+/// was explicitly set apart from the console's own default.
+pub struct WinApiColor {
+    default_attribute: WORD,
+    color_support: Cell<Option<ColorSupport>>,
+}
+
+impl Construct for WinApiColor {
+    fn new() -> Box<WinApiColor> {
+        Box::from(WinApiColor {
+            default_attribute: read_attribute(),
+            color_support: Cell::new(None),
+        })
+    }
+}
+
+impl ITerminalColor for WinApiColor {
+    fn set_fg(&self, fg_color: Color) {
+        let current = self.current_attribute();
+        self.set_attribute((current & !0x000F) | console_attr(quantize(fg_color), false));
+    }
+
+    fn set_bg(&self, bg_color: Color) {
+        let current = self.current_attribute();
+        self.set_attribute((current & !0x00F0) | console_attr(quantize(bg_color), true));
+    }
+
+    fn reset(&self) {
+        self.set_attribute(self.default_attribute);
+    }
+
+    fn reset_fg(&self) {
+        let current = self.current_attribute();
+        self.set_attribute((current & !0x000F) | (self.default_attribute & 0x000F));
+    }
+
+    fn reset_bg(&self) {
+        let current = self.current_attribute();
+        self.set_attribute((current & !0x00F0) | (self.default_attribute & 0x00F0));
+    }
+
+    fn set_attr(&self, attr: Attribute) {
+        let current = self.current_attribute();
+        self.set_attribute(current | attribute_bits(attr));
+    }
+
+    fn reset_attr(&self, attr: Attribute) {
+        let current = self.current_attribute();
+        self.set_attribute(current & !attribute_bits(attr));
+    }
+
+    fn color_support(&self) -> ColorSupport {
+        if let Some(support) = self.color_support.get() {
+            return support;
+        }
+
+        let support = detect_color_support();
+        self.color_support.set(Some(support));
+        support
+    }
+
+    fn snapshot(&self) -> ColorSnapshot {
+        let current = self.current_attribute();
+
+        let mut attrs = Vec::new();
+        if current & FOREGROUND_INTENSITY != 0 {
+            attrs.push(Attribute::Bold);
+        }
+        if current & COMMON_LVB_UNDERSCORE != 0 {
+            attrs.push(Attribute::Underlined);
+        }
+        if current & COMMON_LVB_REVERSE_VIDEO != 0 {
+            attrs.push(Attribute::Reverse);
+        }
+
+        let fg_bits = current & 0x000F;
+        let bg_bits = current & 0x00F0;
+
+        let fg = if fg_bits == self.default_attribute & 0x000F {
+            None
+        } else {
+            Some(color_from_bits(fg_bits))
+        };
+        let bg = if bg_bits == self.default_attribute & 0x00F0 {
+            None
+        } else {
+            Some(color_from_bits(bg_bits >> 4))
+        };
+
+        ColorSnapshot { fg, bg, attrs }
+    }
+}
+
+impl WinApiColor {
+    /// Set the given console text attribute on the current screen buffer.
+    fn set_attribute(&self, attribute: WORD) {
+        unsafe {
+            let handle: HANDLE = GetStdHandle(STD_OUTPUT_HANDLE);
+            SetConsoleTextAttribute(handle, attribute);
+        }
+    }
+
+    /// Read the console text attribute that is currently in effect.
+    fn current_attribute(&self) -> WORD {
+        read_attribute()
+    }
+}
+
+/// Probe the console mode to work out what level of color this terminal actually supports.
+///
+/// This briefly enables `ENABLE_VIRTUAL_TERMINAL_PROCESSING` to tell whether the console
+/// understands it, then restores the original mode. It is only meant to run once per
+/// `WinApiColor` (see `color_support()`, which caches the result) rather than on every
+/// `set_fg`/`set_bg` call, since toggling console mode is a global, process-wide side effect.
+///
+/// Consoles that support VT processing render truecolor escape sequences directly, so there
+/// is no separate 256-color tier to detect here; `Ansi256` is never returned on Windows.
+fn detect_color_support() -> ColorSupport {
+    unsafe {
+        let handle: HANDLE = GetStdHandle(STD_OUTPUT_HANDLE);
+
+        let mut mode: DWORD = 0;
+        if GetConsoleMode(handle, &mut mode) == 0 {
+            return ColorSupport::NoColor;
+        }
+
+        if mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 {
+            return ColorSupport::TrueColor;
+        }
+
+        // Windows 10+ consoles support VT processing even when it is not yet enabled;
+        // probing by enabling it tells us whether this build understands it.
+        if SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0 {
+            SetConsoleMode(handle, mode);
+            return ColorSupport::TrueColor;
+        }
+
+        ColorSupport::Ansi16
+    }
+}
+
+/// Read the console text attribute that is currently in effect.
+fn read_attribute() -> WORD {
+    unsafe {
+        let handle: HANDLE = GetStdHandle(STD_OUTPUT_HANDLE);
+        let mut info: CONSOLE_SCREEN_BUFFER_INFO = ::std::mem::zeroed();
+        GetConsoleScreenBufferInfo(handle, &mut info);
+        info.wAttributes
+    }
+}
+
+/// The console character attribute bits that best approximate the given text attribute.
+/// The windows console only exposes intensity, underline and reverse video; the other
+/// attributes have no equivalent and are silently ignored.
+fn attribute_bits(attr: Attribute) -> WORD {
+    match attr {
+        Attribute::Bold => FOREGROUND_INTENSITY,
+        Attribute::Underlined => COMMON_LVB_UNDERSCORE,
+        Attribute::Reverse => COMMON_LVB_REVERSE_VIDEO,
+        Attribute::Dim | Attribute::Italic | Attribute::SlowBlink | Attribute::Hidden
+        | Attribute::Crossedout => 0,
+    }
+}
+
+/// Quantize the given color down to one of the 15 named console colors in `CONSOLE_COLORS`,
+/// leaving named colors untouched.
+fn quantize(color: Color) -> Color {
+    match color {
+        Color::Rgb { r, g, b } => nearest_console_color(r, g, b),
+        Color::AnsiValue(val) => nearest_console_color_from_ansi(val),
+        named => named,
+    }
+}
+
+/// Find the named console color closest to the given RGB value, measured by squared
+/// Euclidean distance.
+fn nearest_console_color(r: u8, g: u8, b: u8) -> Color {
+    let mut nearest = Color::White;
+    let mut nearest_distance = u32::max_value();
+
+    for &(color, pr, pg, pb) in CONSOLE_COLORS.iter() {
+        let dr = r as i32 - pr as i32;
+        let dg = g as i32 - pg as i32;
+        let db = b as i32 - pb as i32;
+        let distance = (dr * dr + dg * dg + db * db) as u32;
+
+        if distance < nearest_distance {
+            nearest = color;
+            nearest_distance = distance;
+        }
+    }
+
+    nearest
+}
+
+/// Resolve a 256-color palette index (as used by `Color::AnsiValue`) down to a named
+/// console color.
+fn nearest_console_color_from_ansi(val: u8) -> Color {
+    if val < 16 {
+        return match val {
+            0 => Color::Black,
+            1 => Color::DarkRed,
+            2 => Color::DarkGreen,
+            3 => Color::DarkYellow,
+            4 => Color::DarkBlue,
+            5 => Color::DarkMagenta,
+            6 => Color::DarkCyan,
+            7 => Color::Grey,
+            8 => Color::Black,
+            9 => Color::Red,
+            10 => Color::Green,
+            11 => Color::Yellow,
+            12 => Color::Blue,
+            13 => Color::Magenta,
+            14 => Color::Cyan,
+            _ => Color::White,
+        };
+    }
+
+    if val <= 231 {
+        let idx = val - 16;
+        let r = idx / 36;
+        let g = (idx / 6) % 6;
+        let b = idx % 6;
+        return nearest_console_color(r * 51, g * 51, b * 51);
+    }
+
+    let grey = 8 + 10 * (val - 232);
+    nearest_console_color(grey, grey, grey)
+}
+
+/// Translate the low nibble of a `SetConsoleTextAttribute` value back into a named color,
+/// the inverse of `console_attr`.
+fn color_from_bits(bits: WORD) -> Color {
+    let intensity = bits & FOREGROUND_INTENSITY != 0;
+    let rgb = bits & (FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE);
+
+    match (rgb, intensity) {
+        (0, false) => Color::Black,
+        (0, true) => Color::Grey,
+        (FOREGROUND_BLUE, false) => Color::DarkBlue,
+        (FOREGROUND_GREEN, false) => Color::DarkGreen,
+        (FOREGROUND_RED, false) => Color::DarkRed,
+        (b, false) if b == FOREGROUND_GREEN | FOREGROUND_BLUE => Color::DarkCyan,
+        (b, false) if b == FOREGROUND_RED | FOREGROUND_BLUE => Color::DarkMagenta,
+        (b, false) if b == FOREGROUND_RED | FOREGROUND_GREEN => Color::DarkYellow,
+        (b, false) if b == FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE => Color::Grey,
+        (FOREGROUND_BLUE, true) => Color::Blue,
+        (FOREGROUND_GREEN, true) => Color::Green,
+        (FOREGROUND_RED, true) => Color::Red,
+        (b, true) if b == FOREGROUND_GREEN | FOREGROUND_BLUE => Color::Cyan,
+        (b, true) if b == FOREGROUND_RED | FOREGROUND_BLUE => Color::Magenta,
+        (b, true) if b == FOREGROUND_RED | FOREGROUND_GREEN => Color::Yellow,
+        _ => Color::White,
+    }
+}
+
+/// Translate a named console color and fg/bg slot into the `SetConsoleTextAttribute` bits.
+fn console_attr(color: Color, is_background: bool) -> WORD {
+    let bits = match color {
+        Color::Black => 0,
+        Color::DarkBlue => FOREGROUND_BLUE,
+        Color::DarkGreen => FOREGROUND_GREEN,
+        Color::DarkCyan => FOREGROUND_GREEN | FOREGROUND_BLUE,
+        Color::DarkRed => FOREGROUND_RED,
+        Color::DarkMagenta => FOREGROUND_RED | FOREGROUND_BLUE,
+        Color::DarkYellow => FOREGROUND_RED | FOREGROUND_GREEN,
+        Color::Grey => FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE,
+        Color::Blue => FOREGROUND_BLUE | FOREGROUND_INTENSITY,
+        Color::Green => FOREGROUND_GREEN | FOREGROUND_INTENSITY,
+        Color::Cyan => FOREGROUND_GREEN | FOREGROUND_BLUE | FOREGROUND_INTENSITY,
+        Color::Red => FOREGROUND_RED | FOREGROUND_INTENSITY,
+        Color::Magenta => FOREGROUND_RED | FOREGROUND_BLUE | FOREGROUND_INTENSITY,
+        Color::Yellow => FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_INTENSITY,
+        Color::White => FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE | FOREGROUND_INTENSITY,
+        // Rgb/AnsiValue are quantized to a named color before reaching this function.
+        _ => FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE,
+    };
+
+    if is_background {
+        bits << 4
+    } else {
+        bits
+    }
+}
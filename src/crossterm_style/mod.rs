@@ -0,0 +1,13 @@
+//! This module contains the logic to style the terminal, like applying color to the font and
+//! background of some text.
+
+mod color;
+mod object_style;
+mod styled_object;
+
+pub use self::color::{
+    get, paint, Attribute, Color, ColorGuard, ColorSupport, ColorType, ITerminalColor,
+    ParseColorError, TerminalColor,
+};
+pub use self::object_style::ObjectStyle;
+pub use self::styled_object::StyledObject;
@@ -0,0 +1,54 @@
+//! This module contains the logic to store the style that should be applied to a displayable
+//! object, like the foreground color, background color.
+
+use std::fmt;
+
+use super::{Attribute, Color, StyledObject};
+
+/// Struct that stores the style that can later be applied to a displayable object.
+#[derive(Clone)]
+pub struct ObjectStyle {
+    pub fg_color: Option<Color>,
+    pub bg_color: Option<Color>,
+    pub attrs: Vec<Attribute>,
+}
+
+impl ObjectStyle {
+    /// Create a new `ObjectStyle` without any colors or attributes set.
+    pub fn new() -> ObjectStyle {
+        ObjectStyle {
+            fg_color: None,
+            bg_color: None,
+            attrs: Vec::new(),
+        }
+    }
+
+    /// Wrap the given displayable value in a `StyledObject` carrying this style.
+    pub fn apply_to<D>(&self, val: D) -> StyledObject<D>
+    where
+        D: fmt::Display,
+    {
+        StyledObject {
+            object_style: self.clone(),
+            content: val,
+        }
+    }
+
+    /// Set the foreground color of this style.
+    pub fn fg(mut self, color: Color) -> ObjectStyle {
+        self.fg_color = Some(color);
+        self
+    }
+
+    /// Set the background color of this style.
+    pub fn bg(mut self, color: Color) -> ObjectStyle {
+        self.bg_color = Some(color);
+        self
+    }
+
+    /// Add a text attribute, like bold or underlined, to this style.
+    pub fn attr(mut self, attr: Attribute) -> ObjectStyle {
+        self.attrs.push(attr);
+        self
+    }
+}
@@ -0,0 +1,53 @@
+//! This module contains the logic to display a styled object, so that the colors in its
+//! `ObjectStyle` are applied before the content is written and reset afterwards.
+
+use std::fmt;
+
+use super::{get, Attribute, Color, ObjectStyle};
+
+/// Wraps a displayable value together with the `ObjectStyle` that should be applied to it.
+pub struct StyledObject<D> {
+    pub object_style: ObjectStyle,
+    pub content: D,
+}
+
+impl<D> StyledObject<D> {
+    /// Set the foreground color of this styled object.
+    pub fn with(mut self, color: Color) -> StyledObject<D> {
+        self.object_style = self.object_style.fg(color);
+        self
+    }
+
+    /// Set the background color of this styled object.
+    pub fn on(mut self, color: Color) -> StyledObject<D> {
+        self.object_style = self.object_style.bg(color);
+        self
+    }
+
+    /// Add a text attribute, like bold or underlined, to this styled object.
+    pub fn attr(mut self, attr: Attribute) -> StyledObject<D> {
+        self.object_style = self.object_style.attr(attr);
+        self
+    }
+}
+
+impl<D: fmt::Display> fmt::Display for StyledObject<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut colored_terminal = get();
+
+        if let Some(bg) = self.object_style.bg_color {
+            colored_terminal.set_bg(bg);
+        }
+        if let Some(fg) = self.object_style.fg_color {
+            colored_terminal.set_fg(fg);
+        }
+        for attr in self.object_style.attrs.iter() {
+            colored_terminal.set_attr(*attr);
+        }
+
+        write!(f, "{}", self.content)?;
+
+        colored_terminal.reset();
+        Ok(())
+    }
+}
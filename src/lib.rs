@@ -0,0 +1,24 @@
+//! Crossterm is a cross-platform terminal library, that is used to style the terminal.
+//! It provides the same core functionality for both windows and unix systems, by hiding
+//! the platform specific implementation behind one easy to use api.
+
+#[cfg(unix)]
+extern crate atty;
+#[cfg(windows)]
+extern crate kernel32;
+#[cfg(windows)]
+extern crate winapi;
+
+pub mod crossterm_style;
+
+pub use crossterm_style::{
+    get, paint, Attribute, Color, ColorGuard, ColorSupport, ColorType, ObjectStyle,
+    ParseColorError, StyledObject, TerminalColor,
+};
+
+/// This trait is used to construct a boxed, platform specific implementation of some
+/// functionality crossterm provides, like coloring the terminal.
+pub trait Construct {
+    /// Construct a new instance of the implementing type, wrapped in a `Box`.
+    fn new() -> Box<Self>;
+}